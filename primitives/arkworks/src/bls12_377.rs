@@ -0,0 +1,393 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! BLS12-377 pairing and group operations.
+
+#![warn(missing_docs)]
+
+use crate::{
+	msm::pippenger_msm,
+	utils::{deserialization_error, serialize_result, serialize_result_compressed, validate_mode, ArkError},
+};
+use ark_bls12_377::{g1, g2, Bls12_377, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{
+	models::CurveConfig,
+	pairing::{MillerLoopOutput, Pairing},
+	AffineRepr, CurveGroup, Group,
+};
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
+use ark_std::io::Cursor;
+use sp_std::vec::Vec;
+
+/// Compute multi miller loop through arkworks
+pub fn multi_miller_loop(
+	a_vec: Vec<Vec<u8>>,
+	b_vec: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let mode = validate_mode(validate);
+	let g1: Vec<_> = a_vec
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<ark_ec::short_weierstrass::Affine<ark_bls12_377::g1::Config> as CanonicalDeserialize>::deserialize_with_mode(cursor, Compress::No, mode)
+				.map(<Bls12_377 as Pairing>::G1Prepared::from)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let g2: Vec<_> = b_vec
+		.iter()
+		.map(|b| {
+			let cursor = Cursor::new(b);
+			<ark_ec::short_weierstrass::Affine<ark_bls12_377::g2::Config> as CanonicalDeserialize>::deserialize_with_mode(cursor, Compress::No, mode)
+				.map(<Bls12_377 as Pairing>::G2Prepared::from)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = Bls12_377::multi_miller_loop(g1, g2).0;
+
+	Ok(serialize_result(result))
+}
+
+/// Compute final exponentiation through arkworks
+pub fn final_exponentiation(target: Vec<u8>) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(target);
+	let target = <Bls12_377 as Pairing>::TargetField::deserialize_with_mode(
+		cursor,
+		Compress::No,
+		Validate::No,
+	)
+	.map_err(|e| deserialization_error(e, false))?;
+
+	let result = Bls12_377::final_exponentiation(MillerLoopOutput(target))
+		.ok_or(ArkError::PairingError)?
+		.0;
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a scalar multiplication on G2 through arkworks
+pub fn mul_projective_g2(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G2Projective::deserialize_with_mode(cursor, Compress::No, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar);
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a scalar multiplication on G1 through arkworks
+pub fn mul_projective_g1(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G1Projective::deserialize_with_mode(cursor, Compress::No, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar);
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a scalar multiplication on G1 through arkworks
+pub fn mul_affine_g1(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G1Affine::deserialize_with_mode(cursor, Compress::No, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar).into_affine();
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a scalar multiplication on G2 through arkworks
+pub fn mul_affine_g2(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G2Affine::deserialize_with_mode(cursor, Compress::No, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar).into_affine();
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a multi scalar multiplication on G1 through arkworks
+pub fn msm_g1(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<Bls12_377 as Pairing>::G1Affine::deserialize_with_mode(cursor, Compress::No, mode)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<g1::Config as CurveConfig>::ScalarField::deserialize_with_mode(
+				cursor,
+				Compress::No,
+				Validate::No,
+			)
+			.map_err(|e| deserialization_error(e, false))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = pippenger_msm::<G1Projective>(&bases, &scalars);
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a multi scalar multiplication on G2 through arkworks
+pub fn msm_g2(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<Bls12_377 as Pairing>::G2Affine::deserialize_with_mode(cursor, Compress::No, mode)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<g2::Config as CurveConfig>::ScalarField::deserialize_with_mode(
+				cursor,
+				Compress::No,
+				Validate::No,
+			)
+			.map_err(|e| deserialization_error(e, false))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = pippenger_msm::<G2Projective>(&bases, &scalars);
+
+	Ok(serialize_result(result))
+}
+
+/// Compute multi miller loop through arkworks, reading compressed G1/G2 points
+pub fn multi_miller_loop_compressed(
+	a_vec: Vec<Vec<u8>>,
+	b_vec: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let mode = validate_mode(validate);
+	let g1: Vec<_> = a_vec
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<ark_ec::short_weierstrass::Affine<ark_bls12_377::g1::Config> as CanonicalDeserialize>::deserialize_with_mode(cursor, Compress::Yes, mode)
+				.map(<Bls12_377 as Pairing>::G1Prepared::from)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let g2: Vec<_> = b_vec
+		.iter()
+		.map(|b| {
+			let cursor = Cursor::new(b);
+			<ark_ec::short_weierstrass::Affine<ark_bls12_377::g2::Config> as CanonicalDeserialize>::deserialize_with_mode(cursor, Compress::Yes, mode)
+				.map(<Bls12_377 as Pairing>::G2Prepared::from)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = Bls12_377::multi_miller_loop(g1, g2).0;
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a scalar multiplication on G2 through arkworks, reading a compressed base
+pub fn mul_projective_g2_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G2Projective::deserialize_with_mode(cursor, Compress::Yes, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar);
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a scalar multiplication on G1 through arkworks, reading a compressed base
+pub fn mul_projective_g1_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G1Projective::deserialize_with_mode(cursor, Compress::Yes, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar);
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a scalar multiplication on G1 through arkworks, reading a compressed base
+pub fn mul_affine_g1_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G1Affine::deserialize_with_mode(cursor, Compress::Yes, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar).into_affine();
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a scalar multiplication on G2 through arkworks, reading a compressed base
+pub fn mul_affine_g2_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G2Affine::deserialize_with_mode(cursor, Compress::Yes, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar).into_affine();
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a multi scalar multiplication on G1 through arkworks, reading compressed bases
+pub fn msm_g1_compressed(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<Bls12_377 as Pairing>::G1Affine::deserialize_with_mode(cursor, Compress::Yes, mode)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<g1::Config as CurveConfig>::ScalarField::deserialize_with_mode(
+				cursor,
+				Compress::No,
+				Validate::No,
+			)
+			.map_err(|e| deserialization_error(e, false))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = pippenger_msm::<G1Projective>(&bases, &scalars);
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a multi scalar multiplication on G2 through arkworks, reading compressed bases
+pub fn msm_g2_compressed(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<Bls12_377 as Pairing>::G2Affine::deserialize_with_mode(cursor, Compress::Yes, mode)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<g2::Config as CurveConfig>::ScalarField::deserialize_with_mode(
+				cursor,
+				Compress::No,
+				Validate::No,
+			)
+			.map_err(|e| deserialization_error(e, false))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = pippenger_msm::<G2Projective>(&bases, &scalars);
+
+	Ok(serialize_result_compressed(result))
+}
@@ -19,156 +19,389 @@
 
 #![warn(missing_docs)]
 
-use crate::utils::serialize_result;
-use ark_bls12_381::{
-	g1, g2, Bls12_381, Fq12Config, G1Affine, G1Projective, G2Affine, G2Projective,
+use crate::{
+	msm::pippenger_msm,
+	utils::{deserialization_error, serialize_result, serialize_result_compressed, validate_mode, ArkError},
 };
-use ark_ec::{models::CurveConfig, pairing::Pairing, Group};
-use ark_ff::{Fp12ConfigWrapper, QuadExtField, Zero};
+use ark_bls12_381::{g1, g2, Bls12_381, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{
+	hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve},
+	models::CurveConfig,
+	pairing::{MillerLoopOutput, Pairing},
+	AffineRepr, CurveGroup, Group,
+};
+use ark_ff::field_hashers::DefaultFieldHasher;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 use ark_std::io::Cursor;
+use sha2::Sha256;
 use sp_std::vec::Vec;
 
+/// Hash a message to G1 through arkworks, per RFC 9380's
+/// `BLS12381G1_XMD:SHA-256_SSWU_RO_` suite
+pub fn hash_to_g1(message: Vec<u8>, dst: Vec<u8>) -> Result<Vec<u8>, ArkError> {
+	let hasher = MapToCurveBasedHasher::<G1Projective, DefaultFieldHasher<Sha256>, WBMap<g1::Config>>::new(
+		&dst,
+	)
+	.map_err(|_| ArkError::HashToCurveError)?;
+	let result = hasher.hash(&message).map_err(|_| ArkError::HashToCurveError)?;
+
+	Ok(serialize_result(result))
+}
+
+/// Hash a message to G2 through arkworks, per RFC 9380's
+/// `BLS12381G2_XMD:SHA-256_SSWU_RO_` suite
+pub fn hash_to_g2(message: Vec<u8>, dst: Vec<u8>) -> Result<Vec<u8>, ArkError> {
+	let hasher = MapToCurveBasedHasher::<G2Projective, DefaultFieldHasher<Sha256>, WBMap<g2::Config>>::new(
+		&dst,
+	)
+	.map_err(|_| ArkError::HashToCurveError)?;
+	let result = hasher.hash(&message).map_err(|_| ArkError::HashToCurveError)?;
+
+	Ok(serialize_result(result))
+}
+
 /// Compute multi miller loop through arkworks
-pub fn multi_miller_loop(a_vec: Vec<Vec<u8>>, b_vec: Vec<Vec<u8>>) -> Vec<u8> {
-	let _g1: Vec<_> = a_vec
+pub fn multi_miller_loop(
+	a_vec: Vec<Vec<u8>>,
+	b_vec: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let mode = validate_mode(validate);
+	let g1: Vec<_> = a_vec
 		.iter()
 		.map(|a| {
 			let cursor = Cursor::new(a);
-			<ark_ec::short_weierstrass::Affine<ark_bls12_381::g1::Config> as CanonicalDeserialize>::deserialize_uncompressed(cursor)
-			.map(<Bls12_381 as Pairing>::G1Prepared::from)
-			.unwrap()
+			<ark_ec::short_weierstrass::Affine<ark_bls12_381::g1::Config> as CanonicalDeserialize>::deserialize_with_mode(cursor, Compress::No, mode)
+				.map(<Bls12_381 as Pairing>::G1Prepared::from)
+				.map_err(|e| deserialization_error(e, validate))
 		})
-		.collect();
-	let _g2: Vec<_> = b_vec
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let g2: Vec<_> = b_vec
 		.iter()
 		.map(|b| {
 			let cursor = Cursor::new(b);
-			<ark_ec::short_weierstrass::Affine<ark_bls12_381::g2::Config> as CanonicalDeserialize>::deserialize_uncompressed(cursor)
-			.map(<Bls12_381 as Pairing>::G2Prepared::from)
-			.unwrap()
+			<ark_ec::short_weierstrass::Affine<ark_bls12_381::g2::Config> as CanonicalDeserialize>::deserialize_with_mode(cursor, Compress::No, mode)
+				.map(<Bls12_381 as Pairing>::G2Prepared::from)
+				.map_err(|e| deserialization_error(e, validate))
 		})
-		.collect();
+		.collect::<Result<Vec<_>, ArkError>>()?;
 
-	let result = QuadExtField::<Fp12ConfigWrapper<Fq12Config>>::zero();
+	let result = Bls12_381::multi_miller_loop(g1, g2).0;
 
-	serialize_result(result)
+	Ok(serialize_result(result))
 }
 
 /// Compute final exponentiation through arkworks
-pub fn final_exponentiation(target: Vec<u8>) -> Vec<u8> {
+pub fn final_exponentiation(target: Vec<u8>) -> Result<Vec<u8>, ArkError> {
 	let cursor = Cursor::new(target);
-	let _target = <Bls12_381 as Pairing>::TargetField::deserialize_with_mode(
+	let target = <Bls12_381 as Pairing>::TargetField::deserialize_with_mode(
 		cursor,
 		Compress::No,
 		Validate::No,
 	)
-	.unwrap();
+	.map_err(|e| deserialization_error(e, false))?;
 
-	let result = QuadExtField::<Fp12ConfigWrapper<Fq12Config>>::zero();
+	let result = Bls12_381::final_exponentiation(MillerLoopOutput(target))
+		.ok_or(ArkError::PairingError)?
+		.0;
 
-	serialize_result(result)
+	Ok(serialize_result(result))
 }
 
 /// Compute a scalar multiplication on G2 through arkworks
-pub fn mul_projective_g2(base: Vec<u8>, scalar: Vec<u8>) -> Vec<u8> {
+pub fn mul_projective_g2(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
 	let cursor = Cursor::new(base);
-	let _base = G2Projective::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let base = G2Projective::deserialize_with_mode(cursor, Compress::No, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
 
 	let cursor = Cursor::new(scalar);
-	let _scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
 
-	let result = G2Projective::generator();
+	let result = base.mul_bigint(scalar);
 
-	serialize_result(result)
+	Ok(serialize_result(result))
 }
 
-/// Compute a scalar multiplication on G2 through arkworks
-pub fn mul_projective_g1(base: Vec<u8>, scalar: Vec<u8>) -> Vec<u8> {
+/// Compute a scalar multiplication on G1 through arkworks
+pub fn mul_projective_g1(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
 	let cursor = Cursor::new(base);
-	let _base = G1Projective::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let base = G1Projective::deserialize_with_mode(cursor, Compress::No, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
 
 	let cursor = Cursor::new(scalar);
-	let _scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
 
-	let result = G1Projective::generator();
+	let result = base.mul_bigint(scalar);
 
-	serialize_result(result)
+	Ok(serialize_result(result))
 }
 
-/// Compute a scalar multiplication on G2 through arkworks
-pub fn mul_affine_g1(base: Vec<u8>, scalar: Vec<u8>) -> Vec<u8> {
+/// Compute a scalar multiplication on G1 through arkworks
+pub fn mul_affine_g1(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
 	let cursor = Cursor::new(base);
-	let _base = G1Affine::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let base = G1Affine::deserialize_with_mode(cursor, Compress::No, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
 
 	let cursor = Cursor::new(scalar);
-	let _scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
 
-	let result = G1Projective::generator();
+	let result = base.mul_bigint(scalar).into_affine();
 
-	serialize_result(result)
+	Ok(serialize_result(result))
 }
 
 /// Compute a scalar multiplication on G2 through arkworks
-pub fn mul_affine_g2(base: Vec<u8>, scalar: Vec<u8>) -> Vec<u8> {
+pub fn mul_affine_g2(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
 	let cursor = Cursor::new(base);
-	let _base = G2Affine::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let base = G2Affine::deserialize_with_mode(cursor, Compress::No, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
 
 	let cursor = Cursor::new(scalar);
-	let _scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
 
-	let result = G2Projective::generator();
+	let result = base.mul_bigint(scalar).into_affine();
 
-	serialize_result(result)
+	Ok(serialize_result(result))
 }
 
-/// Compute a multi scalar multiplication on G! through arkworks
-pub fn msm_g1(bases: Vec<Vec<u8>>, scalars: Vec<Vec<u8>>) -> Vec<u8> {
-	let _bases: Vec<_> = bases
+/// Compute a multi scalar multiplication on G1 through arkworks
+pub fn msm_g1(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<Bls12_381 as Pairing>::G1Affine::deserialize_with_mode(cursor, Compress::No, mode)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
 		.iter()
 		.map(|a| {
 			let cursor = Cursor::new(a);
-			<Bls12_381 as Pairing>::G1Affine::deserialize_with_mode(
+			<g1::Config as CurveConfig>::ScalarField::deserialize_with_mode(
 				cursor,
 				Compress::No,
 				Validate::No,
 			)
-			.unwrap()
+			.map_err(|e| deserialization_error(e, false))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = pippenger_msm::<G1Projective>(&bases, &scalars);
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a multi scalar multiplication on G2 through arkworks
+pub fn msm_g2(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<Bls12_381 as Pairing>::G2Affine::deserialize_with_mode(cursor, Compress::No, mode)
+				.map_err(|e| deserialization_error(e, validate))
 		})
-		.collect();
-	let _scalars: Vec<_> = scalars
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
 		.iter()
 		.map(|a| {
 			let cursor = Cursor::new(a);
-			<g1::Config as CurveConfig>::ScalarField::deserialize_with_mode(
+			<g2::Config as CurveConfig>::ScalarField::deserialize_with_mode(
 				cursor,
 				Compress::No,
 				Validate::No,
 			)
-			.unwrap()
+			.map_err(|e| deserialization_error(e, false))
 		})
-		.collect();
+		.collect::<Result<Vec<_>, ArkError>>()?;
 
-	let result = G1Projective::generator();
+	let result = pippenger_msm::<G2Projective>(&bases, &scalars);
 
-	serialize_result(result)
+	Ok(serialize_result(result))
 }
 
-/// Compute a multi scalar multiplication on G! through arkworks
-pub fn msm_g2(bases: Vec<Vec<u8>>, scalars: Vec<Vec<u8>>) -> Vec<u8> {
-	let _bases: Vec<_> = bases
+/// Compute multi miller loop through arkworks, reading compressed G1/G2 points
+pub fn multi_miller_loop_compressed(
+	a_vec: Vec<Vec<u8>>,
+	b_vec: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let mode = validate_mode(validate);
+	let g1: Vec<_> = a_vec
 		.iter()
 		.map(|a| {
 			let cursor = Cursor::new(a);
-			<Bls12_381 as Pairing>::G2Affine::deserialize_with_mode(
+			<ark_ec::short_weierstrass::Affine<ark_bls12_381::g1::Config> as CanonicalDeserialize>::deserialize_with_mode(cursor, Compress::Yes, mode)
+				.map(<Bls12_381 as Pairing>::G1Prepared::from)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let g2: Vec<_> = b_vec
+		.iter()
+		.map(|b| {
+			let cursor = Cursor::new(b);
+			<ark_ec::short_weierstrass::Affine<ark_bls12_381::g2::Config> as CanonicalDeserialize>::deserialize_with_mode(cursor, Compress::Yes, mode)
+				.map(<Bls12_381 as Pairing>::G2Prepared::from)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = Bls12_381::multi_miller_loop(g1, g2).0;
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a scalar multiplication on G2 through arkworks, reading a compressed base
+pub fn mul_projective_g2_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G2Projective::deserialize_with_mode(cursor, Compress::Yes, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar);
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a scalar multiplication on G1 through arkworks, reading a compressed base
+pub fn mul_projective_g1_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G1Projective::deserialize_with_mode(cursor, Compress::Yes, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar);
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a scalar multiplication on G1 through arkworks, reading a compressed base
+pub fn mul_affine_g1_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G1Affine::deserialize_with_mode(cursor, Compress::Yes, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar).into_affine();
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a scalar multiplication on G2 through arkworks, reading a compressed base
+pub fn mul_affine_g2_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = G2Affine::deserialize_with_mode(cursor, Compress::Yes, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar).into_affine();
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a multi scalar multiplication on G1 through arkworks, reading compressed bases
+pub fn msm_g1_compressed(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<Bls12_381 as Pairing>::G1Affine::deserialize_with_mode(cursor, Compress::Yes, mode)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<g1::Config as CurveConfig>::ScalarField::deserialize_with_mode(
 				cursor,
 				Compress::No,
 				Validate::No,
 			)
-			.unwrap()
+			.map_err(|e| deserialization_error(e, false))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = pippenger_msm::<G1Projective>(&bases, &scalars);
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a multi scalar multiplication on G2 through arkworks, reading compressed bases
+pub fn msm_g2_compressed(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<Bls12_381 as Pairing>::G2Affine::deserialize_with_mode(cursor, Compress::Yes, mode)
+				.map_err(|e| deserialization_error(e, validate))
 		})
-		.collect();
-	let _scalars: Vec<_> = scalars
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
 		.iter()
 		.map(|a| {
 			let cursor = Cursor::new(a);
@@ -177,11 +410,11 @@ pub fn msm_g2(bases: Vec<Vec<u8>>, scalars: Vec<Vec<u8>>) -> Vec<u8> {
 				Compress::No,
 				Validate::No,
 			)
-			.unwrap()
+			.map_err(|e| deserialization_error(e, false))
 		})
-		.collect();
+		.collect::<Result<Vec<_>, ArkError>>()?;
 
-	let result = G2Projective::generator();
+	let result = pippenger_msm::<G2Projective>(&bases, &scalars);
 
-	serialize_result(result)
+	Ok(serialize_result_compressed(result))
 }
@@ -19,87 +19,254 @@
 
 #![warn(missing_docs)]
 
-use crate::utils::serialize_result;
+use crate::{
+	msm::pippenger_msm,
+	utils::{deserialization_error, serialize_result, serialize_result_compressed, validate_mode, ArkError},
+};
 use ark_ec::{
 	models::CurveConfig, short_weierstrass::Affine as SWAffine, twisted_edwards,
-	twisted_edwards::Affine as TEAffine, Group,
+	twisted_edwards::Affine as TEAffine, AffineRepr, CurveGroup, Group,
 };
 use ark_ed_on_bls12_381::{EdwardsProjective, JubjubConfig, SWProjective};
 use ark_serialize::{CanonicalDeserialize, Compress, Validate};
 use ark_std::io::Cursor;
 use sp_std::vec::Vec;
 
-/// Compute a scalar multiplication on G2 through arkworks
-pub fn sw_mul_projective(base: Vec<u8>, scalar: Vec<u8>) -> Vec<u8> {
+/// Compute a scalar multiplication on the short Weierstrass Jubjub group through arkworks
+pub fn sw_mul_projective(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
 	let cursor = Cursor::new(base);
-	let _base = ark_ec::short_weierstrass::Projective::<JubjubConfig>::deserialize_with_mode(
+	let base = ark_ec::short_weierstrass::Projective::<JubjubConfig>::deserialize_with_mode(
 		cursor,
 		Compress::No,
-		Validate::No,
+		validate_mode(validate),
 	)
-	.unwrap();
+	.map_err(|e| deserialization_error(e, validate))?;
 	let cursor = Cursor::new(scalar);
-	let _scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
 
-	let result = SWProjective::generator();
+	let result = base.mul_bigint(scalar);
 
-	serialize_result(result)
+	Ok(serialize_result(result))
 }
 
-/// Compute a scalar multiplication through arkworks
-pub fn sw_mul_affine(base: Vec<u8>, scalar: Vec<u8>) -> Vec<u8> {
+/// Compute a scalar multiplication on the short Weierstrass Jubjub group through arkworks
+pub fn sw_mul_affine(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
 	let cursor = Cursor::new(base);
-	let _base = SWAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::No, Validate::No)
-		.unwrap();
+	let base = SWAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::No, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
 	let cursor = Cursor::new(scalar);
-	let _scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
 
-	let result = SWProjective::generator();
+	let result = base.mul_bigint(scalar).into_affine();
 
-	serialize_result(result)
+	Ok(serialize_result(result))
 }
 
-/// Compute a scalar multiplication on G2 through arkworks
-pub fn te_mul_projective(base: Vec<u8>, scalar: Vec<u8>) -> Vec<u8> {
+/// Compute a scalar multiplication on the twisted Edwards Jubjub group through arkworks
+pub fn te_mul_projective(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
 	let cursor = Cursor::new(base);
-	let _base = twisted_edwards::Projective::<JubjubConfig>::deserialize_with_mode(
+	let base = twisted_edwards::Projective::<JubjubConfig>::deserialize_with_mode(
 		cursor,
 		Compress::No,
-		Validate::No,
+		validate_mode(validate),
+	)
+	.map_err(|e| deserialization_error(e, validate))?;
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar);
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a scalar multiplication on the twisted Edwards Jubjub group through arkworks
+pub fn te_mul_affine(base: Vec<u8>, scalar: Vec<u8>, validate: bool) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = TEAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::No, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar).into_affine();
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a multi scalar multiplication on the twisted Edwards Jubjub group through arkworks
+pub fn te_msm(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			TEAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::No, mode)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<JubjubConfig as CurveConfig>::ScalarField::deserialize_with_mode(
+				cursor,
+				Compress::No,
+				Validate::No,
+			)
+			.map_err(|e| deserialization_error(e, false))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = pippenger_msm::<EdwardsProjective>(&bases, &scalars);
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a multi scalar multiplication on the short Weierstrass Jubjub group through arkworks
+pub fn sw_msm(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			SWAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::No, mode)
+				.map_err(|e| deserialization_error(e, validate))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
+		.iter()
+		.map(|a| {
+			let cursor = Cursor::new(a);
+			<JubjubConfig as CurveConfig>::ScalarField::deserialize_with_mode(
+				cursor,
+				Compress::No,
+				Validate::No,
+			)
+			.map_err(|e| deserialization_error(e, false))
+		})
+		.collect::<Result<Vec<_>, ArkError>>()?;
+
+	let result = pippenger_msm::<SWProjective>(&bases, &scalars);
+
+	Ok(serialize_result(result))
+}
+
+/// Compute a scalar multiplication on the short Weierstrass Jubjub group through arkworks, reading a compressed base
+pub fn sw_mul_projective_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = ark_ec::short_weierstrass::Projective::<JubjubConfig>::deserialize_with_mode(
+		cursor,
+		Compress::Yes,
+		validate_mode(validate),
+	)
+	.map_err(|e| deserialization_error(e, validate))?;
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar);
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a scalar multiplication on the short Weierstrass Jubjub group through arkworks, reading a compressed base
+pub fn sw_mul_affine_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = SWAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::Yes, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
+	let cursor = Cursor::new(scalar);
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
+
+	let result = base.mul_bigint(scalar).into_affine();
+
+	Ok(serialize_result_compressed(result))
+}
+
+/// Compute a scalar multiplication on the twisted Edwards Jubjub group through arkworks, reading a compressed base
+pub fn te_mul_projective_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	let cursor = Cursor::new(base);
+	let base = twisted_edwards::Projective::<JubjubConfig>::deserialize_with_mode(
+		cursor,
+		Compress::Yes,
+		validate_mode(validate),
 	)
-	.unwrap();
+	.map_err(|e| deserialization_error(e, validate))?;
 	let cursor = Cursor::new(scalar);
-	let _scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
 
-	let result = EdwardsProjective::generator();
+	let result = base.mul_bigint(scalar);
 
-	serialize_result(result)
+	Ok(serialize_result_compressed(result))
 }
 
-/// Compute a scalar multiplication through arkworks
-pub fn te_mul_affine(base: Vec<u8>, scalar: Vec<u8>) -> Vec<u8> {
+/// Compute a scalar multiplication on the twisted Edwards Jubjub group through arkworks, reading a compressed base
+pub fn te_mul_affine_compressed(
+	base: Vec<u8>,
+	scalar: Vec<u8>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
 	let cursor = Cursor::new(base);
-	let _base = TEAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::No, Validate::No)
-		.unwrap();
+	let base = TEAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::Yes, validate_mode(validate))
+		.map_err(|e| deserialization_error(e, validate))?;
 	let cursor = Cursor::new(scalar);
-	let _scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No).unwrap();
+	let scalar = Vec::<u64>::deserialize_with_mode(cursor, Compress::No, Validate::No)
+		.map_err(|e| deserialization_error(e, false))?;
 
-	let result = EdwardsProjective::generator();
+	let result = base.mul_bigint(scalar).into_affine();
 
-	serialize_result(result)
+	Ok(serialize_result_compressed(result))
 }
 
-/// Compute a multi scalar multiplication on G! through arkworks
-pub fn te_msm(bases: Vec<Vec<u8>>, scalars: Vec<Vec<u8>>) -> Vec<u8> {
-	let _bases: Vec<_> = bases
+/// Compute a multi scalar multiplication on the twisted Edwards Jubjub group through arkworks, reading compressed bases
+pub fn te_msm_compressed(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
 		.iter()
 		.map(|a| {
 			let cursor = Cursor::new(a);
-			TEAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::No, Validate::No)
-				.unwrap()
+			TEAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::Yes, mode)
+				.map_err(|e| deserialization_error(e, validate))
 		})
-		.collect();
-	let _scalars: Vec<_> = scalars
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
 		.iter()
 		.map(|a| {
 			let cursor = Cursor::new(a);
@@ -108,26 +275,34 @@ pub fn te_msm(bases: Vec<Vec<u8>>, scalars: Vec<Vec<u8>>) -> Vec<u8> {
 				Compress::No,
 				Validate::No,
 			)
-			.unwrap()
+			.map_err(|e| deserialization_error(e, false))
 		})
-		.collect();
+		.collect::<Result<Vec<_>, ArkError>>()?;
 
-	let result = EdwardsProjective::generator();
+	let result = pippenger_msm::<EdwardsProjective>(&bases, &scalars);
 
-	serialize_result(result)
+	Ok(serialize_result_compressed(result))
 }
 
-/// Compute a multi scalar multiplication on G! through arkworks
-pub fn sw_msm(bases: Vec<Vec<u8>>, scalars: Vec<Vec<u8>>) -> Vec<u8> {
-	let _bases: Vec<_> = bases
+/// Compute a multi scalar multiplication on the short Weierstrass Jubjub group through arkworks, reading compressed bases
+pub fn sw_msm_compressed(
+	bases: Vec<Vec<u8>>,
+	scalars: Vec<Vec<u8>>,
+	validate: bool,
+) -> Result<Vec<u8>, ArkError> {
+	if bases.len() != scalars.len() {
+		return Err(ArkError::DeserializationError);
+	}
+	let mode = validate_mode(validate);
+	let bases: Vec<_> = bases
 		.iter()
 		.map(|a| {
 			let cursor = Cursor::new(a);
-			SWAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::No, Validate::No)
-				.unwrap()
+			SWAffine::<JubjubConfig>::deserialize_with_mode(cursor, Compress::Yes, mode)
+				.map_err(|e| deserialization_error(e, validate))
 		})
-		.collect();
-	let _scalars: Vec<_> = scalars
+		.collect::<Result<Vec<_>, ArkError>>()?;
+	let scalars: Vec<_> = scalars
 		.iter()
 		.map(|a| {
 			let cursor = Cursor::new(a);
@@ -136,11 +311,11 @@ pub fn sw_msm(bases: Vec<Vec<u8>>, scalars: Vec<Vec<u8>>) -> Vec<u8> {
 				Compress::No,
 				Validate::No,
 			)
-			.unwrap()
+			.map_err(|e| deserialization_error(e, false))
 		})
-		.collect();
+		.collect::<Result<Vec<_>, ArkError>>()?;
 
-	let result = SWProjective::generator();
+	let result = pippenger_msm::<SWProjective>(&bases, &scalars);
 
-	serialize_result(result)
+	Ok(serialize_result_compressed(result))
 }
@@ -0,0 +1,112 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shared multi-scalar multiplication, reused by every curve module's
+//! `msm_*` host function.
+
+#![warn(missing_docs)]
+
+use ark_ec::CurveGroup;
+use ark_ff::{BigInteger, PrimeField};
+use sp_std::vec::Vec;
+
+/// Below this many terms, the fixed overhead of bucketing is not worth it,
+/// so we fall back to an exact naive sum.
+const NAIVE_THRESHOLD: usize = 32;
+
+/// Multi-scalar multiplication via the Pippenger bucket method.
+///
+/// Splits each scalar into `ceil(b/c)` `c`-bit windows, accumulates the
+/// bases for a window into `2^c - 1` buckets keyed by the window's digit,
+/// reduces each window with the running-sum trick, and finally combines
+/// the window sums from most to least significant, doubling by `c`
+/// between them.
+///
+/// Callers must ensure `bases.len() == scalars.len()`; this is a private
+/// invariant enforced by the `msm_*`/`te_msm`/`sw_msm` host functions before
+/// they call in, not re-checked here.
+pub fn pippenger_msm<G: CurveGroup>(bases: &[G::Affine], scalars: &[G::ScalarField]) -> G {
+	let n = bases.len();
+
+	if n < NAIVE_THRESHOLD {
+		return naive_msm::<G>(bases, scalars);
+	}
+
+	let num_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+	let c = window_bits(n);
+	let num_windows = (num_bits + c - 1) / c;
+	let bigints: Vec<_> = scalars.iter().map(|s| s.into_bigint()).collect();
+
+	let window_sums: Vec<G> = (0..num_windows)
+		.map(|w| {
+			let mut buckets = sp_std::vec![G::zero(); (1 << c) - 1];
+			for (base, scalar) in bases.iter().zip(&bigints) {
+				let digit = window_digit(scalar, w, c);
+				if digit != 0 {
+					buckets[digit - 1] += base;
+				}
+			}
+
+			let mut running = G::zero();
+			let mut acc = G::zero();
+			for bucket in buckets.into_iter().rev() {
+				running += bucket;
+				acc += running;
+			}
+			acc
+		})
+		.collect();
+
+	window_sums.into_iter().rev().fold(G::zero(), |mut acc, window_sum| {
+		for _ in 0..c {
+			acc.double_in_place();
+		}
+		acc += window_sum;
+		acc
+	})
+}
+
+/// Exact, non-bucketed scalar sum, used when `n` is too small to amortise
+/// the bucket method's overhead.
+fn naive_msm<G: CurveGroup>(bases: &[G::Affine], scalars: &[G::ScalarField]) -> G {
+	bases
+		.iter()
+		.zip(scalars)
+		.fold(G::zero(), |acc, (base, scalar)| acc + base.mul_bigint(scalar.into_bigint()))
+}
+
+/// Window width `c`, roughly `log2(n) - 3` bits, clamped to `[1, 32]`.
+///
+/// Only ever called for `n >= NAIVE_THRESHOLD` (smaller inputs go through
+/// `naive_msm`), so the two thresholds are kept in terms of the same
+/// constant rather than risking them drifting apart.
+fn window_bits(n: usize) -> usize {
+	debug_assert!(n >= NAIVE_THRESHOLD);
+	(ark_std::log2(n) as usize).saturating_sub(3).clamp(1, 32)
+}
+
+/// The `c`-bit digit of `scalar` in window `w`.
+fn window_digit(scalar: &impl BigInteger, w: usize, c: usize) -> usize {
+	let offset = w * c;
+	let mut digit = 0usize;
+	for i in 0..c {
+		if scalar.get_bit(offset + i) {
+			digit |= 1 << i;
+		}
+	}
+	digit
+}
@@ -0,0 +1,83 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shared errors and (de)serialization helpers used by every curve module's
+//! host functions.
+
+#![warn(missing_docs)]
+
+use ark_serialize::{CanonicalSerialize, Compress, SerializationError};
+use codec::{Decode, Encode};
+use sp_std::vec::Vec;
+
+/// Errors a host function can return instead of panicking on malformed or
+/// untrusted input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum ArkError {
+	/// The input bytes did not decode to a point or scalar of the expected
+	/// type.
+	DeserializationError,
+	/// The decoded point failed the on-curve or prime-order subgroup check.
+	ValidationError,
+	/// The pairing's final exponentiation had no inverse.
+	PairingError,
+	/// Building the hasher for the given domain-separation tag, or hashing
+	/// the message to a curve point, failed.
+	HashToCurveError,
+}
+
+/// Serialize `elem` in the uncompressed wire format the host functions use
+/// by default.
+pub fn serialize_result<T: CanonicalSerialize>(elem: T) -> Vec<u8> {
+	let mut serialized = sp_std::vec![0u8; elem.serialized_size(Compress::No)];
+	elem.serialize_with_mode(&mut serialized[..], Compress::No)
+		.unwrap();
+	serialized
+}
+
+/// Serialize `elem` in the compressed wire format, for callers that accept
+/// the extra on-host decompression cost in exchange for a smaller buffer.
+pub fn serialize_result_compressed<T: CanonicalSerialize>(elem: T) -> Vec<u8> {
+	let mut serialized = sp_std::vec![0u8; elem.serialized_size(Compress::Yes)];
+	elem.serialize_with_mode(&mut serialized[..], Compress::Yes)
+		.unwrap();
+	serialized
+}
+
+/// The `Validate` mode a caller requested: `Yes` to run on-curve/subgroup
+/// checks on untrusted input, `No` to skip them for input already known to
+/// be valid.
+pub fn validate_mode(validate: bool) -> ark_serialize::Validate {
+	if validate {
+		ark_serialize::Validate::Yes
+	} else {
+		ark_serialize::Validate::No
+	}
+}
+
+/// Map an arkworks deserialization error onto the coarser [`ArkError`]
+/// distinction between a malformed encoding and a point that failed
+/// validation. `validate` must reflect whether the decode that produced `e`
+/// actually ran the on-curve/subgroup check (i.e. was passed
+/// `Validate::Yes`) — otherwise `SerializationError::InvalidData` means the
+/// encoding itself was malformed, not that validation failed.
+pub fn deserialization_error(e: SerializationError, validate: bool) -> ArkError {
+	match e {
+		SerializationError::InvalidData if validate => ArkError::ValidationError,
+		_ => ArkError::DeserializationError,
+	}
+}